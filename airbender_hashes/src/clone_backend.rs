@@ -0,0 +1,139 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use forge::{DirEntry, ForgeBackend};
+use git2::Repository;
+use std::path::{Path, PathBuf};
+
+/// Scans tags by cloning the repo once and reading tags/trees straight out
+/// of the local object store, instead of one contents-API call per tag.
+/// Built for repos with hundreds of tags (zksync-airbender) where the API
+/// backend is slow and easily rate-limited.
+///
+/// git2 doesn't expose partial-clone filters yet, so this is a full bare
+/// clone rather than the blobless clone the API-weary would ideally want --
+/// still a single network round trip instead of one per tag.
+pub struct GitCloneBackend {
+    owner: String,
+    repo_name: String,
+    repo: Repository,
+}
+
+impl GitCloneBackend {
+    /// Clones `owner/repo` into `clone_dir` if it isn't already there,
+    /// otherwise fetches tags/refs into the existing clone.
+    pub fn open_or_clone(owner: String, repo_name: String, clone_dir: &Path) -> Result<Self> {
+        let url = format!("https://github.com/{owner}/{repo_name}.git");
+        let repo = if clone_dir.join("HEAD").exists() {
+            let repo = Repository::open_bare(clone_dir)
+                .with_context(|| format!("opening bare clone at {}", clone_dir.display()))?;
+            fetch_all(&repo, &url)?;
+            repo
+        } else {
+            if let Some(parent) = clone_dir.parent() {
+                std::fs::create_dir_all(parent).ok();
+            }
+            git2::build::RepoBuilder::new()
+                .bare(true)
+                .clone(&url, clone_dir)
+                .with_context(|| format!("cloning {url} into {}", clone_dir.display()))?
+        };
+        Ok(Self {
+            owner,
+            repo_name,
+            repo,
+        })
+    }
+
+    fn tree_at<'a>(&'a self, git_ref: &str) -> Result<git2::Tree<'a>> {
+        let obj = self
+            .repo
+            .revparse_single(&format!("refs/tags/{git_ref}"))
+            .or_else(|_| self.repo.revparse_single(git_ref))
+            .with_context(|| format!("resolving ref {git_ref}"))?;
+        let commit = obj.peel_to_commit()?;
+        Ok(commit.tree()?)
+    }
+}
+
+fn fetch_all(repo: &Repository, url: &str) -> Result<()> {
+    let mut remote = repo
+        .find_remote("origin")
+        .or_else(|_| repo.remote_anonymous(url))?;
+    remote
+        .fetch(
+            &["+refs/tags/*:refs/tags/*", "+refs/heads/*:refs/heads/*"],
+            None,
+            None,
+        )
+        .with_context(|| format!("fetching {url}"))?;
+    Ok(())
+}
+
+#[async_trait]
+impl ForgeBackend for GitCloneBackend {
+    async fn list_dir(&self, path: &str, git_ref: &str) -> Result<Option<Vec<DirEntry>>> {
+        let tree = match self.tree_at(git_ref) {
+            Ok(tree) => tree,
+            Err(_) => return Ok(None),
+        };
+        let entry = match tree.get_path(Path::new(path)) {
+            Ok(entry) => entry,
+            Err(_) => return Ok(None),
+        };
+        let subtree = match entry.to_object(&self.repo)?.into_tree() {
+            Ok(subtree) => subtree,
+            Err(_) => return Ok(None),
+        };
+        Ok(Some(
+            subtree
+                .iter()
+                .map(|e| DirEntry {
+                    name: e.name().unwrap_or_default().to_string(),
+                    path: format!("{path}/{}", e.name().unwrap_or_default()),
+                    is_dir: e.kind() == Some(git2::ObjectType::Tree),
+                })
+                .collect(),
+        ))
+    }
+
+    async fn list_tags(&self) -> Result<Vec<String>> {
+        Ok(self
+            .repo
+            .tag_names(None)?
+            .iter()
+            .flatten()
+            .map(str::to_string)
+            .collect())
+    }
+
+    async fn fetch_raw(&self, path: &str, git_ref: &str) -> Result<Option<String>> {
+        let tree = match self.tree_at(git_ref) {
+            Ok(tree) => tree,
+            Err(_) => return Ok(None),
+        };
+        let entry = match tree.get_path(Path::new(path)) {
+            Ok(entry) => entry,
+            Err(_) => return Ok(None),
+        };
+        let blob = match entry.to_object(&self.repo)?.into_blob() {
+            Ok(blob) => blob,
+            Err(_) => return Ok(None),
+        };
+        let text = std::str::from_utf8(blob.content())
+            .with_context(|| format!("{path}@{git_ref}: not valid UTF-8"))?
+            .to_string();
+        Ok(Some(text))
+    }
+
+    fn browse_url(&self, path: &str, git_ref: &str) -> String {
+        format!(
+            "https://github.com/{}/{}/blob/{}/{}",
+            self.owner, self.repo_name, git_ref, path
+        )
+    }
+}
+
+/// Default local clone directory for `owner/repo`, alongside the ETag cache.
+pub fn default_clone_dir(owner: &str, repo: &str) -> PathBuf {
+    PathBuf::from(format!(".zk-wtf-clone-{owner}-{repo}"))
+}
@@ -1,8 +1,7 @@
 use anyhow::{anyhow, Context, Result};
 use chrono::Utc;
 use futures::stream::{FuturesUnordered, StreamExt};
-use reqwest::header::{ACCEPT, AUTHORIZATION};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use serde_json::Value;
 use std::{
     collections::{BTreeMap, HashMap},
@@ -10,7 +9,33 @@ use std::{
     path::PathBuf,
     sync::Arc,
 };
-use tokio::sync::Semaphore;
+use tokio::sync::{Mutex, Semaphore};
+
+mod clone_backend;
+use clone_backend::GitCloneBackend;
+use extract::RuleSet;
+use forge::{cache::EtagCache, ForgeBackend, ForgeKind};
+
+/// How tags are acquired: one contents-API call per tag (`Api`, the
+/// default) or a single local clone scanned offline (`Clone`), which is
+/// much faster for repos with hundreds of tags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AcquisitionMode {
+    Api,
+    Clone,
+}
+
+impl std::str::FromStr for AcquisitionMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "api" => Ok(AcquisitionMode::Api),
+            "clone" => Ok(AcquisitionMode::Clone),
+            other => Err(anyhow!("unknown mode '{other}', expected api|clone")),
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 struct Config {
@@ -21,6 +46,34 @@ struct Config {
     out_path: PathBuf,
     parallel: usize,
     max_tags: Option<usize>, // optional limit for testing
+    cache_path: PathBuf,
+    forge: ForgeKind,
+    endpoint: Option<String>, // required for --forge gitea
+    mode: AcquisitionMode,
+    clone_dir: Option<PathBuf>,
+    rules_file: Option<PathBuf>,
+    select: Vec<(String, String)>,      // (label, json-pointer)
+    match_regex: Vec<(String, String)>, // (label, pattern)
+}
+
+/// Looks up `bytecode_hash_hex` and `params_hex` and emits them under the
+/// `<tag>/<file>/bytecode` and `<tag>/<file>/params` keys and labels of the
+/// original hardcoded `find_string_by_key` lookups. Unlike that lookup,
+/// every occurrence of a key is emitted, and a nested occurrence keeps its
+/// nesting in the output key.
+fn default_rules() -> RuleSet {
+    let mut rules = RuleSet::default();
+    rules.push_match_key(
+        "bytecode_hash_hex".to_string(),
+        "Bytecode hash".to_string(),
+        Some("bytecode".to_string()),
+    );
+    rules.push_match_key(
+        "params_hex".to_string(),
+        "Verification params hash".to_string(),
+        Some("params".to_string()),
+    );
+    rules
 }
 
 impl Default for Config {
@@ -33,30 +86,18 @@ impl Default for Config {
             out_path: PathBuf::from("airbender_verifier_index.json"),
             parallel: 16,
             max_tags: None,
+            cache_path: PathBuf::from(".zk-wtf-cache.json"),
+            forge: ForgeKind::Github,
+            endpoint: None,
+            mode: AcquisitionMode::Api,
+            clone_dir: None,
+            rules_file: None,
+            select: Vec::new(),
+            match_regex: Vec::new(),
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct TagItem {
-    name: String,
-}
-
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "lowercase")]
-enum GhItemType {
-    File,
-    Dir,
-}
-
-#[derive(Debug, Deserialize)]
-struct GhContentItem {
-    name: String,
-    path: String,
-    #[serde(rename = "type")]
-    kind: GhItemType,
-}
-
 #[derive(Debug, Serialize)]
 struct Output {
     source: String,
@@ -98,6 +139,31 @@ async fn main() -> Result<()> {
                         .context("--max-tags usize")?,
                 )
             }
+            "--cache-path" => {
+                cfg.cache_path = PathBuf::from(args.next().context("--cache-path value")?)
+            }
+            "--forge" => cfg.forge = args.next().context("--forge value")?.parse()?,
+            "--endpoint" => cfg.endpoint = Some(args.next().context("--endpoint value")?),
+            "--mode" => cfg.mode = args.next().context("--mode value")?.parse()?,
+            "--clone-dir" => {
+                cfg.clone_dir = Some(PathBuf::from(args.next().context("--clone-dir value")?))
+            }
+            "--rules-file" => {
+                cfg.rules_file = Some(PathBuf::from(args.next().context("--rules-file value")?))
+            }
+            "--select" => {
+                let spec = args
+                    .next()
+                    .context("--select requires LABEL=POINTER value")?;
+                let (label, pointer) = spec
+                    .split_once('=')
+                    .context("--select value must be LABEL=POINTER")?;
+                cfg.select.push((label.to_string(), pointer.to_string()));
+            }
+            "--match-regex" => {
+                let pattern = args.next().context("--match-regex value")?;
+                cfg.match_regex.push(("Match".to_string(), pattern));
+            }
             _ => eprintln!("Unknown arg: {a}"),
         }
     }
@@ -109,7 +175,49 @@ async fn main() -> Result<()> {
         ))
         .build()?;
 
-    let tags = list_tags(&client, &cfg).await?;
+    let cache = Arc::new(Mutex::new(EtagCache::load(&cfg.cache_path)));
+    let backend: Arc<dyn ForgeBackend> = match cfg.mode {
+        AcquisitionMode::Api => {
+            let token = env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty());
+            Arc::from(forge::build_backend(
+                cfg.forge,
+                cfg.endpoint.clone(),
+                cfg.owner.clone(),
+                cfg.repo.clone(),
+                token,
+                client,
+                cache.clone(),
+            )?)
+        }
+        AcquisitionMode::Clone => {
+            let clone_dir = cfg
+                .clone_dir
+                .clone()
+                .unwrap_or_else(|| clone_backend::default_clone_dir(&cfg.owner, &cfg.repo));
+            Arc::new(GitCloneBackend::open_or_clone(
+                cfg.owner.clone(),
+                cfg.repo.clone(),
+                &clone_dir,
+            )?)
+        }
+    };
+
+    let rules = if let Some(path) = &cfg.rules_file {
+        RuleSet::load(path)?
+    } else if cfg.select.is_empty() && cfg.match_regex.is_empty() {
+        default_rules()
+    } else {
+        let mut rules = RuleSet::default();
+        for (label, pointer) in &cfg.select {
+            rules.push_select(label.clone(), pointer.clone());
+        }
+        for (label, pattern) in &cfg.match_regex {
+            rules.push_match_regex(pattern.clone(), label.clone());
+        }
+        rules
+    };
+
+    let tags = list_tags(&backend, &cfg).await?;
     println!("Scanning {} tags", tags.len());
 
     // For each tag, list JSON files in subpath, then fetch & parse each
@@ -117,7 +225,7 @@ async fn main() -> Result<()> {
     let mut tag_entries: HashMap<String, OutputItem> = HashMap::new();
 
     for tag in tags {
-        let json_files = list_json_files_for_tag(&client, &cfg, &tag).await?;
+        let json_files = list_json_files_for_tag(&backend, &cfg, &tag).await?;
         if json_files.is_empty() {
             // Some tags may not have the path yet â€” that's fine.
             continue;
@@ -125,13 +233,14 @@ async fn main() -> Result<()> {
 
         let mut tasks = FuturesUnordered::new();
         for item in json_files {
-            let client = client.clone();
+            let backend = backend.clone();
             let cfg = cfg.clone();
+            let rules = rules.clone();
             let tag_clone = tag.clone();
             let sem = sem.clone();
             tasks.push(tokio::spawn(async move {
                 let _permit = sem.acquire().await.expect("semaphore");
-                fetch_and_extract(&client, &cfg, &tag_clone, &item).await
+                fetch_and_extract(&backend, &cfg, &rules, &tag_clone, &item).await
             }));
         }
 
@@ -166,45 +275,22 @@ async fn main() -> Result<()> {
         .with_context(|| format!("writing {}", cfg.out_path.display()))?;
 
     println!("Wrote {}", cfg.out_path.display());
+
+    cache.lock().await.save(&cfg.cache_path)?;
+
     Ok(())
 }
 
-async fn list_tags(client: &reqwest::Client, cfg: &Config) -> Result<Vec<String>> {
-    // GET /repos/{owner}/{repo}/tags?per_page=100&page=N
-    let mut page = 1usize;
-    let per_page = 100usize;
-    let mut out: Vec<String> = Vec::new();
-
-    loop {
-        let url = format!(
-            "https://api.github.com/repos/{}/{}/tags?per_page={}&page={}",
-            cfg.owner, cfg.repo, per_page, page
-        );
-        let mut req = client
-            .get(&url)
-            .header(ACCEPT, "application/vnd.github+json");
-        if let Ok(token) = env::var("GITHUB_TOKEN") {
-            if !token.is_empty() {
-                req = req.header(AUTHORIZATION, format!("Bearer {}", token));
-            }
-        }
-        let resp = req.send().await?.error_for_status()?;
-        let batch: Vec<TagItem> = resp.json().await?;
-        if batch.is_empty() {
-            break;
-        }
-        for t in batch {
-            if t.name.starts_with(&cfg.tags_prefix) {
-                out.push(t.name);
-            }
-        }
-        if let Some(max) = cfg.max_tags {
-            if out.len() >= max {
-                out.truncate(max);
-                break;
-            }
-        }
-        page += 1;
+async fn list_tags(backend: &Arc<dyn ForgeBackend>, cfg: &Config) -> Result<Vec<String>> {
+    let mut out: Vec<String> = backend
+        .list_tags()
+        .await?
+        .into_iter()
+        .filter(|name| name.starts_with(&cfg.tags_prefix))
+        .collect();
+
+    if let Some(max) = cfg.max_tags {
+        out.truncate(max);
     }
 
     if out.is_empty() {
@@ -214,117 +300,55 @@ async fn list_tags(client: &reqwest::Client, cfg: &Config) -> Result<Vec<String>
 }
 
 async fn list_json_files_for_tag(
-    client: &reqwest::Client,
+    backend: &Arc<dyn ForgeBackend>,
     cfg: &Config,
     tag: &str,
-) -> Result<Vec<GhContentItem>> {
-    // GET /repos/{owner}/{repo}/contents/{path}?ref={tag}
-    let url = format!(
-        "https://api.github.com/repos/{}/{}/contents/{}?ref={}",
-        cfg.owner, cfg.repo, cfg.subpath, tag
-    );
-    let mut req = client
-        .get(&url)
-        .header(ACCEPT, "application/vnd.github+json");
-    if let Ok(token) = env::var("GITHUB_TOKEN") {
-        if !token.is_empty() {
-            req = req.header(AUTHORIZATION, format!("Bearer {}", token));
-        }
-    }
-    let resp = req.send().await?;
-    if resp.status().as_u16() == 404 {
-        return Ok(vec![]);
-    } // path may not exist in this tag
-    let items: Vec<GhContentItem> = resp.error_for_status()?.json().await?;
-    Ok(items
+) -> Result<Vec<forge::DirEntry>> {
+    let entries = backend
+        .list_dir(&cfg.subpath, tag)
+        .await?
+        .unwrap_or_default();
+    Ok(entries
         .into_iter()
-        .filter(|i| matches!(i.kind, GhItemType::File) && i.name.ends_with(".json"))
+        .filter(|i| !i.is_dir && i.name.ends_with(".json"))
         .collect())
 }
 
 async fn fetch_and_extract(
-    client: &reqwest::Client,
+    backend: &Arc<dyn ForgeBackend>,
     cfg: &Config,
+    rules: &RuleSet,
     tag: &str,
-    item: &GhContentItem,
+    item: &forge::DirEntry,
 ) -> Result<HashMap<String, OutputItem>> {
-    let raw_url = format!(
-        "https://raw.githubusercontent.com/{}/{}/{}/{}",
-        cfg.owner, cfg.repo, tag, item.path
-    );
-    let user_url = format!(
-        "https://github.com/{}/{}/blob/{}/{}",
-        cfg.owner, cfg.repo, tag, item.path
-    );
-    let mut req = client.get(&raw_url);
-    if let Ok(token) = env::var("GITHUB_TOKEN") {
-        if !token.is_empty() {
-            req = req.header(AUTHORIZATION, format!("Bearer {}", token));
-        }
-    }
-    let text = req.send().await?.error_for_status()?.text().await?;
+    let user_url = backend.browse_url(&item.path, tag);
+    let text = backend
+        .fetch_raw(&item.path, tag)
+        .await?
+        .with_context(|| format!("{}: 404 Not Found", item.path))?;
 
     let val: Value =
         serde_json::from_str(&text).with_context(|| format!("{}: invalid JSON", item.path))?;
 
-    let bytecode = find_string_by_key(&val, "bytecode_hash_hex");
-    let params = find_string_by_key(&val, "params_hex");
-
     let key = format!("{}/{}", tag, item.name);
+    let extracted = extract::extract(&key, &val, rules, "/")?;
 
-    let mut result = HashMap::new();
-    if let Some(bytecode) = &bytecode {
-        result.insert(
-            format!("{}/bytecode", key),
-            OutputItem {
-                value: bytecode.clone(),
-                url: user_url.clone(),
-                description: format!(
-                    "Bytecode hash for {} for tag {} in {}",
-                    item.name, tag, cfg.repo
-                ),
-            },
-        );
-    }
-    if let Some(params) = &params {
-        result.insert(
-            format!("{}/params", key),
-            OutputItem {
-                value: params.clone(),
-                url: user_url.clone(),
-                description: format!(
-                    "Verification params hash for {} for tag {} in {}",
-                    item.name, tag, cfg.repo
-                ),
-            },
-        );
-    }
-    Ok(result)
-}
+    let result = extracted
+        .into_iter()
+        .map(|found| {
+            (
+                found.path.clone(),
+                OutputItem {
+                    value: found.value,
+                    url: user_url.clone(),
+                    description: format!(
+                        "{} for {} for tag {} in {}",
+                        found.label, item.name, tag, cfg.repo
+                    ),
+                },
+            )
+        })
+        .collect();
 
-fn find_string_by_key(v: &Value, key: &str) -> Option<String> {
-    match v {
-        Value::Object(map) => {
-            for (k, vv) in map {
-                if k == key {
-                    if let Value::String(s) = vv {
-                        return Some(s.clone());
-                    }
-                }
-                if let Some(found) = find_string_by_key(vv, key) {
-                    return Some(found);
-                }
-            }
-            None
-        }
-        Value::Array(arr) => {
-            for x in arr {
-                if let Some(found) = find_string_by_key(x, key) {
-                    return Some(found);
-                }
-            }
-            None
-        }
-        _ => None,
-    }
+    Ok(result)
 }
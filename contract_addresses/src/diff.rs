@@ -0,0 +1,94 @@
+use crate::{Output, OutputItem};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A single key's value and/or implementation changing between two
+/// snapshots. `old`/`new` always hold `value` (the proxy address itself,
+/// which is stable across most upgrades); `*_implementation` are only set
+/// when the implementation changed, which is how a DiamondProxy upgrade
+/// shows up here even though `value` stays the same.
+#[derive(Debug, Clone, Serialize)]
+pub struct ValueChange {
+    pub old: String,
+    pub new: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_implementation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_implementation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_contract_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_contract_name: Option<String>,
+}
+
+/// Everything that moved between two `Output` snapshots: keys that appeared,
+/// keys that disappeared, and keys whose value changed in place.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangeLog {
+    pub fetched_at: chrono::DateTime<chrono::Utc>,
+    pub added: BTreeMap<String, OutputItem>,
+    pub removed: BTreeMap<String, OutputItem>,
+    pub changed: BTreeMap<String, ValueChange>,
+}
+
+impl ChangeLog {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compares `previous.items` against `current.items`, keyed by the same
+/// output-item key (e.g. `"mainnet DiamondProxy - 324 zkSync Era"`).
+pub fn diff(previous: &Output, current: &Output) -> ChangeLog {
+    let mut added = BTreeMap::new();
+    let mut changed = BTreeMap::new();
+    for (key, item) in &current.items {
+        match previous.items.get(key) {
+            None => {
+                added.insert(key.clone(), item.clone());
+            }
+            Some(prev_item)
+                if prev_item.value != item.value
+                    || prev_item.implementation != item.implementation
+                    || prev_item.contract_name != item.contract_name =>
+            {
+                let implementation_changed = prev_item.implementation != item.implementation;
+                let contract_name_changed = prev_item.contract_name != item.contract_name;
+                changed.insert(
+                    key.clone(),
+                    ValueChange {
+                        old: prev_item.value.clone(),
+                        new: item.value.clone(),
+                        old_implementation: implementation_changed
+                            .then(|| prev_item.implementation.clone())
+                            .flatten(),
+                        new_implementation: implementation_changed
+                            .then(|| item.implementation.clone())
+                            .flatten(),
+                        old_contract_name: contract_name_changed
+                            .then(|| prev_item.contract_name.clone())
+                            .flatten(),
+                        new_contract_name: contract_name_changed
+                            .then(|| item.contract_name.clone())
+                            .flatten(),
+                    },
+                );
+            }
+            Some(_) => {}
+        }
+    }
+
+    let removed = previous
+        .items
+        .iter()
+        .filter(|(key, _)| !current.items.contains_key(*key))
+        .map(|(key, item)| (key.clone(), item.clone()))
+        .collect();
+
+    ChangeLog {
+        fetched_at: current.fetched_at,
+        added,
+        removed,
+        changed,
+    }
+}
@@ -0,0 +1,168 @@
+use alloy::primitives::Address;
+use anyhow::Result;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// How long a resolved contract's explorer metadata stays valid before we'll
+/// hit the API again. Verified source/proxy wiring changes rarely enough
+/// that a day is a safe default.
+const CACHE_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// On-disk cache entry: `data` plus the unix timestamp it stops being valid.
+/// Expired entries are treated as a cache miss rather than returned stale.
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEnvelope<T> {
+    expiry: u64,
+    data: T,
+}
+
+/// Verified-contract info resolved from a block-explorer's `getsourcecode`
+/// endpoint: the contract name, compiler version, and -- for a proxy -- the
+/// implementation address it currently delegates to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractMetadata {
+    pub name: String,
+    pub compiler_version: String,
+    pub implementation: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExplorerEnvelope {
+    status: String,
+    message: String,
+    result: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct SourceCodeResult {
+    #[serde(rename = "ContractName")]
+    contract_name: String,
+    #[serde(rename = "CompilerVersion")]
+    compiler_version: String,
+    #[serde(rename = "Implementation", default)]
+    implementation: String,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn cache_path(cache_dir: &Path, resource: &str, address: &str) -> PathBuf {
+    cache_dir
+        .join(resource)
+        .join(format!("{}.json", address.to_lowercase()))
+}
+
+fn read_cache<T: DeserializeOwned>(cache_dir: &Path, resource: &str, address: &str) -> Option<T> {
+    let data = fs::read_to_string(cache_path(cache_dir, resource, address)).ok()?;
+    let envelope: CacheEnvelope<T> = serde_json::from_str(&data).ok()?;
+    if envelope.expiry < now() {
+        return None;
+    }
+    Some(envelope.data)
+}
+
+fn write_cache<T: Serialize>(
+    cache_dir: &Path,
+    resource: &str,
+    address: &str,
+    data: &T,
+) -> Result<()> {
+    let path = cache_path(cache_dir, resource, address);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let envelope = CacheEnvelope {
+        expiry: now() + CACHE_TTL_SECS,
+        data,
+    };
+    fs::write(&path, serde_json::to_vec_pretty(&envelope)?)?;
+    Ok(())
+}
+
+/// Looks up verified-contract metadata from an Etherscan/Blockscout-style
+/// explorer API, caching responses on disk under `cache_dir` so repeated
+/// runs don't re-hit a rate-limited endpoint for addresses we already know.
+#[derive(Clone)]
+pub struct ExplorerClient {
+    client: reqwest::Client,
+    cache_dir: PathBuf,
+}
+
+impl ExplorerClient {
+    pub fn new(cache_dir: PathBuf) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            cache_dir,
+        }
+    }
+
+    /// Resolves `address` via `base_url`'s `getsourcecode` action. Returns
+    /// `None` if the explorer has no verified source for it (or no
+    /// `base_url` was configured for this ecosystem at all).
+    pub async fn fetch_metadata(
+        &self,
+        base_url: Option<&str>,
+        api_key: Option<&str>,
+        address: Address,
+    ) -> Result<Option<ContractMetadata>> {
+        let key = format!("{address:#x}");
+        if let Some(cached) = read_cache::<ContractMetadata>(&self.cache_dir, "sources", &key) {
+            return Ok(Some(cached));
+        }
+
+        let Some(base_url) = base_url else {
+            return Ok(None);
+        };
+
+        let mut url = format!(
+            "{}/api?module=contract&action=getsourcecode&address={}",
+            base_url.trim_end_matches('/'),
+            key
+        );
+        if let Some(api_key) = api_key {
+            url.push_str("&apikey=");
+            url.push_str(api_key);
+        }
+
+        let envelope: ExplorerEnvelope = self
+            .client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if envelope.status != "1" {
+            eprintln!(
+                "[warn] explorer lookup for {key} failed: {}",
+                envelope.message
+            );
+            return Ok(None);
+        }
+
+        let results: Vec<SourceCodeResult> = serde_json::from_value(envelope.result)?;
+        let Some(result) = results.into_iter().next() else {
+            return Ok(None);
+        };
+        if result.contract_name.is_empty() {
+            return Ok(None); // address exists but isn't verified
+        }
+
+        let metadata = ContractMetadata {
+            name: result.contract_name,
+            compiler_version: result.compiler_version,
+            implementation: (!result.implementation.is_empty()).then_some(result.implementation),
+        };
+
+        write_cache(&self.cache_dir, "sources", &key, &metadata)?;
+        Ok(Some(metadata))
+    }
+}
@@ -1,9 +1,29 @@
-use alloy::{primitives::Address, providers::ProviderBuilder, sol};
-use anyhow::{Context, Result};
+use alloy::{
+    primitives::{address, Address, B256},
+    providers::{Provider, ProviderBuilder, WsConnect},
+    rpc::types::Filter,
+    sol,
+    sol_types::{SolCall, SolEvent},
+};
+use anyhow::{anyhow, Context, Result};
 use chrono::Utc;
-use serde::Serialize;
+use futures::stream::{FuturesUnordered, StreamExt};
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
-use std::{env, fs, path::PathBuf, str::FromStr};
+use std::sync::Arc;
+use std::{env, fs, io::Write, path::PathBuf, str::FromStr, time::Duration};
+use tokio::sync::Mutex;
+
+mod diff;
+mod explorer;
+mod proof;
+use explorer::ExplorerClient;
+use proof::TrustedBlock;
+
+/// Canonical Multicall3 deployment address, identical across every chain
+/// that has it deployed (all the L1s here, and -- per `Ecosystem::multicall3`
+/// -- potentially the gateway too).
+const MULTICALL3_ADDRESS: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
 
 sol! {
     #[sol(rpc)]
@@ -18,14 +38,64 @@ sol! {
 
         function assetRouter() external view returns (address);
 
+        // Matches the deployed era-contracts Bridgehub ABI's chain
+        // registration event, `NewChain`: `chainId` is indexed for
+        // `eth_getLogs`/`eth_subscribe` topic filtering, `zkChain` is not.
+        event NewChain(uint256 indexed chainId, address zkChain);
+    }
+
+    #[sol(rpc)]
+    interface IMulticall3 {
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+
+        struct Result {
+            bool success;
+            bytes returnData;
+        }
 
+        function aggregate3(Call3[] calldata calls) external payable returns (Result[] memory returnData);
+    }
+}
 
+/// Decodes one `Multicall3.aggregate3` result as `C`'s return value,
+/// surfacing a reverted or undecodable call as an error rather than
+/// panicking, so a single bad call degrades to a `[warn]` instead of
+/// aborting the whole batch.
+fn decode_call_result<C: SolCall>(
+    result: Option<&IMulticall3::Result>,
+    label: &str,
+) -> Result<C::Return> {
+    let result = result.ok_or_else(|| anyhow!("{label} missing from aggregate3 response"))?;
+    if !result.success {
+        return Err(anyhow!("{label} call reverted"));
     }
+    C::abi_decode_returns(&result.returnData, true)
+        .with_context(|| format!("decoding {label} result"))
 }
 
 #[derive(Debug, Clone)]
 struct Config {
     out: PathBuf,
+    explorer_cache_dir: PathBuf,
+    /// Compare a one-off fetch against a previously written snapshot and
+    /// print what changed, instead of just overwriting `out`.
+    diff: Option<PathBuf>,
+    /// Re-fetch every `N` seconds and append a changelog entry to `out`
+    /// each time something changes, instead of running once.
+    watch: Option<u64>,
+    /// Pin proof verification to this block hash instead of each
+    /// ecosystem's latest block.
+    trusted_block_hash: Option<B256>,
+    /// Stream `NewChain` registrations over WebSocket instead of running
+    /// once, for every ecosystem with a `ws_rpc` configured.
+    subscribe: bool,
+    /// Block to start backfilling `NewChain` logs from on `--subscribe`
+    /// startup, before following live events.
+    start_block: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -33,122 +103,304 @@ struct Ecosystem {
     name: String,
     rpc: String,
     bridgehub: Address,
+    /// Base URL of this ecosystem's block-explorer API (Etherscan/Blockscout
+    /// compatible), e.g. `https://api-sepolia.etherscan.io`. `None` skips
+    /// metadata resolution for this ecosystem.
+    explorer_api: Option<String>,
+    explorer_api_key: Option<String>,
+    /// Multicall3 deployment to batch this ecosystem's reads through.
+    /// `MULTICALL3_ADDRESS` on every L1; the gateway may need its own.
+    multicall3: Address,
+    /// WebSocket RPC endpoint used by `--subscribe` to follow this
+    /// ecosystem's `NewChain` events via `eth_subscribe`. `None` skips
+    /// subscription for this ecosystem.
+    ws_rpc: Option<String>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             out: PathBuf::from("contract_addresses.json"),
+            explorer_cache_dir: PathBuf::from(".zk-wtf-explorer-cache"),
+            diff: None,
+            watch: None,
+            trusted_block_hash: None,
+            subscribe: false,
+            start_block: None,
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug)]
 struct ChainItem {
     chain_id: String,
-    zk_chain_address: String,
+    zk_chain_address: Address,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct OutputItem {
     value: String,
     url: String,
     description: String,
+    contract_name: Option<String>,
+    compiler_version: Option<String>,
+    implementation: Option<String>,
+    /// Whether an account exists at `value`'s address under the trusted
+    /// block's `stateRoot`, checked via `eth_getProof` (see
+    /// `proof::verify_account`). Deliberately NOT named `verified`: it does
+    /// not prove that `value` is the address a contract's storage actually
+    /// claims it is -- that would need a storage-trie proof of the specific
+    /// slot, and we don't know the real Bridgehub/Diamond-proxy storage
+    /// layout well enough to name one. `value` itself still comes from an
+    /// `eth_call` merely pinned to the same trusted block as this check.
+    account_exists: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Output {
     source: String,
     fetched_at: chrono::DateTime<chrono::Utc>,
     items: BTreeMap<String, OutputItem>,
 }
 
+/// Derives a human-browsable explorer URL from an `explorer_api` base URL.
+/// Etherscan/Blockscout-style explorers serve their JSON API off an
+/// `api.`/`api-` subdomain of the UI host (`https://api-sepolia.etherscan.io`
+/// -> `https://sepolia.etherscan.io`, `https://api.etherscan.io` ->
+/// `https://etherscan.io`, `https://api.era-gateway-stage.explorer.zksync.dev`
+/// -> `https://era-gateway-stage.explorer.zksync.dev`), so each ecosystem's
+/// link points at its own explorer instead of always `etherscan.io`.
+fn explorer_browse_url(explorer_api: &str) -> String {
+    explorer_api.replacen("api-", "", 1).replacen("api.", "", 1)
+}
+
+/// Builds an `OutputItem` for `address`, attaching verified-contract
+/// metadata (name, compiler, proxy implementation) from `ecosystem`'s
+/// explorer when available, and an `eth_getProof`-backed `account_exists`
+/// flag checked against `trusted`. See `OutputItem::account_exists` for
+/// exactly what that flag does and doesn't guarantee. Lookup/verification
+/// failures are logged and leave their fields `None`/`false` rather than
+/// failing the whole run.
+async fn to_output_item<P: Provider>(
+    provider: &P,
+    explorer: &ExplorerClient,
+    ecosystem: &Ecosystem,
+    address: Address,
+    trusted: &TrustedBlock,
+    description: String,
+) -> OutputItem {
+    let value = format!("0x{:x}", address);
+    let metadata = explorer
+        .fetch_metadata(
+            ecosystem.explorer_api.as_deref(),
+            ecosystem.explorer_api_key.as_deref(),
+            address,
+        )
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("[warn] explorer lookup for {value} failed: {e:#}");
+            None
+        });
+    let account_exists = proof::verify_account(provider, address, &[], trusted).await;
+
+    let browse_base = ecosystem
+        .explorer_api
+        .as_deref()
+        .map(explorer_browse_url)
+        .unwrap_or_else(|| "https://etherscan.io".to_string());
+
+    OutputItem {
+        url: format!("{browse_base}/address/{value}"),
+        contract_name: metadata.as_ref().map(|m| m.name.clone()),
+        compiler_version: metadata.as_ref().map(|m| m.compiler_version.clone()),
+        implementation: metadata.and_then(|m| m.implementation),
+        account_exists,
+        value,
+        description,
+    }
+}
+
 async fn fetch_bridgehub_chains(
-    rpc_url: &str,
-    ecosystem: &str,
-    bridgehub: Address,
+    ecosystem: &Ecosystem,
+    explorer: &ExplorerClient,
     chain_mapping: &HashMap<String, String>,
+    trusted_block_hash: Option<B256>,
 ) -> Result<HashMap<String, OutputItem>> {
-    let provider = ProviderBuilder::new().on_http(rpc_url.parse()?);
-    let hub = IBridgehub::new(bridgehub, provider.clone());
-
-    // Try to get chain IDs first
-    let chain_ids = hub.getAllZKChainChainIDs().call().await?;
+    let provider = ProviderBuilder::new().on_http(ecosystem.rpc.parse()?);
+    let hub = IBridgehub::new(ecosystem.bridgehub, provider.clone());
+    let multicall = IMulticall3::new(ecosystem.multicall3, provider.clone());
+    let trusted = TrustedBlock::resolve(&provider, trusted_block_hash).await?;
+
+    // Pin every read to `trusted.number` so the values we check via
+    // `proof::verify_account` below are actually the ones backing the
+    // `stateRoot` we checked -- reading "latest" here while proving
+    // against a possibly-different pinned block would make
+    // `account_exists` meaningless.
+    let chain_ids = hub
+        .getAllZKChainChainIDs()
+        .block(trusted.number.into())
+        .call()
+        .await?
+        ._0;
+
+    // Batch every per-chain `getZKChain` plus the three ecosystem-level
+    // reads into a single `aggregate3` round trip instead of
+    // `chain_ids.len() + 3` sequential `eth_call`s. `allowFailure: true`
+    // means one bad chain ID doesn't sink the others.
+    let mut calls: Vec<IMulticall3::Call3> = chain_ids
+        .iter()
+        .map(|id| IMulticall3::Call3 {
+            target: ecosystem.bridgehub,
+            allowFailure: true,
+            callData: IBridgehub::getZKChainCall { _chainId: *id }
+                .abi_encode()
+                .into(),
+        })
+        .collect();
+    calls.push(IMulticall3::Call3 {
+        target: ecosystem.bridgehub,
+        allowFailure: true,
+        callData: IBridgehub::sharedBridgeCall {}.abi_encode().into(),
+    });
+    calls.push(IMulticall3::Call3 {
+        target: ecosystem.bridgehub,
+        allowFailure: true,
+        callData: IBridgehub::adminCall {}.abi_encode().into(),
+    });
+    calls.push(IMulticall3::Call3 {
+        target: ecosystem.bridgehub,
+        allowFailure: true,
+        callData: IBridgehub::assetRouterCall {}.abi_encode().into(),
+    });
+
+    let num_calls = chain_ids.len() + 3;
+    let mut results = multicall
+        .aggregate3(calls)
+        .block(trusted.number.into())
+        .call()
+        .await?
+        ._0
+        .into_iter();
+    if results.len() != num_calls {
+        eprintln!(
+            "[warn] aggregate3 returned {} results for {num_calls} calls, truncated response",
+            results.len()
+        );
+    }
 
-    // Fetch each chain address
-    let mut items = Vec::with_capacity(chain_ids._0.len());
-    for id in chain_ids._0 {
-        match hub.getZKChain(id).call().await {
+    let mut items = Vec::with_capacity(chain_ids.len());
+    for id in &chain_ids {
+        let call_result = results.next();
+        match decode_call_result::<IBridgehub::getZKChainCall>(call_result.as_ref(), "getZKChain") {
             Ok(addr) => items.push(ChainItem {
                 chain_id: id.to_string(),
-                zk_chain_address: format!("0x{:x}", addr._0),
+                zk_chain_address: addr._0,
             }),
-            Err(e) => {
-                eprintln!("[warn] getZKChain({id}) failed: {e:#}");
-            }
+            Err(e) => eprintln!("[warn] getZKChain({id}) failed: {e:#}"),
         }
     }
-
-    let mut result: HashMap<String, OutputItem> = items
-        .into_iter()
-        .map(|item| {
-            let chain_name = chain_mapping
-                .get(&item.chain_id)
-                .cloned()
-                .unwrap_or_else(|| format!("chain_{}", item.chain_id));
-
-            (
-                format!(
-                    "{} DiamondProxy - {} {}",
-                    ecosystem, item.chain_id, chain_name
-                ),
-                OutputItem {
-                    value: item.zk_chain_address.clone(),
-                    url: format!("https://etherscan.io/address/{}", item.zk_chain_address),
-                    description: format!("Diamond Proxy for {}", item.chain_id),
-                },
+    let shared_bridge_result = results.next();
+    let admin_result = results.next();
+    let asset_router_result = results.next();
+
+    let mut result: HashMap<String, OutputItem> = HashMap::new();
+    for item in items {
+        let chain_name = chain_mapping
+            .get(&item.chain_id)
+            .cloned()
+            .unwrap_or_else(|| format!("chain_{}", item.chain_id));
+
+        let description = format!("Diamond Proxy for {}", item.chain_id);
+        result.insert(
+            format!(
+                "{} DiamondProxy - {} {}",
+                ecosystem.name, item.chain_id, chain_name
+            ),
+            to_output_item(
+                &provider,
+                explorer,
+                ecosystem,
+                item.zk_chain_address,
+                &trusted,
+                description,
             )
-        })
-        .collect();
+            .await,
+        );
+    }
 
     // insert bridgehub address as well
     result.insert(
-        format!("{} Bridgehub", ecosystem),
-        OutputItem {
-            value: format!("0x{:x}", bridgehub),
-            url: format!("https://etherscan.io/address/{}", bridgehub),
-            description: "Bridgehub contract address".to_string(),
-        },
-    );
-    let shared_bridge = hub.sharedBridge().call().await?;
-    result.insert(
-        format!("{} SharedBridge", ecosystem),
-        OutputItem {
-            value: format!("0x{:x}", shared_bridge._0),
-            url: format!("https://etherscan.io/address/{}", shared_bridge._0),
-            description: "Shared Bridge contract address".to_string(),
-        },
+        format!("{} Bridgehub", ecosystem.name),
+        to_output_item(
+            &provider,
+            explorer,
+            ecosystem,
+            ecosystem.bridgehub,
+            &trusted,
+            "Bridgehub contract address".to_string(),
+        )
+        .await,
     );
 
-    let admin = hub.admin().call().await?;
-    result.insert(
-        format!("{} Admin", ecosystem),
-        OutputItem {
-            value: format!("0x{:x}", admin._0),
-            url: format!("https://etherscan.io/address/{}", admin._0),
-            description: "Admin contract address".to_string(),
-        },
-    );
+    match decode_call_result::<IBridgehub::sharedBridgeCall>(
+        shared_bridge_result.as_ref(),
+        "sharedBridge",
+    ) {
+        Ok(shared_bridge) => {
+            result.insert(
+                format!("{} SharedBridge", ecosystem.name),
+                to_output_item(
+                    &provider,
+                    explorer,
+                    ecosystem,
+                    shared_bridge._0,
+                    &trusted,
+                    "Shared Bridge contract address".to_string(),
+                )
+                .await,
+            );
+        }
+        Err(e) => eprintln!("[warn] sharedBridge() failed: {e:#}"),
+    }
 
-    let asset_router = hub.assetRouter().call().await?;
-    result.insert(
-        format!("{} AssetRouter", ecosystem),
-        OutputItem {
-            value: format!("0x{:x}", asset_router._0),
-            url: format!("https://etherscan.io/address/{}", asset_router._0),
-            description: "Asset Router contract address".to_string(),
-        },
-    );
+    match decode_call_result::<IBridgehub::adminCall>(admin_result.as_ref(), "admin") {
+        Ok(admin) => {
+            result.insert(
+                format!("{} Admin", ecosystem.name),
+                to_output_item(
+                    &provider,
+                    explorer,
+                    ecosystem,
+                    admin._0,
+                    &trusted,
+                    "Admin contract address".to_string(),
+                )
+                .await,
+            );
+        }
+        Err(e) => eprintln!("[warn] admin() failed: {e:#}"),
+    }
+
+    match decode_call_result::<IBridgehub::assetRouterCall>(
+        asset_router_result.as_ref(),
+        "assetRouter",
+    ) {
+        Ok(asset_router) => {
+            result.insert(
+                format!("{} AssetRouter", ecosystem.name),
+                to_output_item(
+                    &provider,
+                    explorer,
+                    ecosystem,
+                    asset_router._0,
+                    &trusted,
+                    "Asset Router contract address".to_string(),
+                )
+                .await,
+            );
+        }
+        Err(e) => eprintln!("[warn] assetRouter() failed: {e:#}"),
+    }
 
     Ok(result)
 }
@@ -163,10 +415,45 @@ async fn main() -> Result<()> {
     while let Some(a) = args.next() {
         match a.as_str() {
             "--out" => cfg.out = PathBuf::from(args.next().context("--out requires value")?),
+            "--explorer-cache-dir" => {
+                cfg.explorer_cache_dir =
+                    PathBuf::from(args.next().context("--explorer-cache-dir requires value")?)
+            }
+            "--diff" => {
+                cfg.diff = Some(PathBuf::from(args.next().context("--diff requires value")?))
+            }
+            "--watch" => {
+                cfg.watch = Some(
+                    args.next()
+                        .context("--watch requires value")?
+                        .parse()
+                        .context("--watch must be seconds (u64)")?,
+                )
+            }
+            "--trusted-block-hash" => {
+                cfg.trusted_block_hash = Some(
+                    args.next()
+                        .context("--trusted-block-hash requires value")?
+                        .parse()
+                        .context("--trusted-block-hash must be a 0x-prefixed hash")?,
+                )
+            }
+            "--subscribe" => cfg.subscribe = true,
+            "--start-block" => {
+                cfg.start_block = Some(
+                    args.next()
+                        .context("--start-block requires value")?
+                        .parse()
+                        .context("--start-block must be a u64")?,
+                )
+            }
             other => eprintln!("Unknown arg: {other}"),
         }
     }
 
+    let explorer_api_key = env::var("ETHERSCAN_API_KEY").ok();
+    let explorer = ExplorerClient::new(cfg.explorer_cache_dir.clone());
+
     // Load chain mapping.
     let chain_mapping: HashMap<String, String> = {
         let file_path = "../data/chains.json";
@@ -199,61 +486,371 @@ async fn main() -> Result<()> {
             rpc: "https://rpc.era-gateway-stage.zksync.dev/".into(),
             bridgehub: Address::from_str("0x0000000000000000000000000000000000010002").unwrap(),
             name: "stage gateway".into(),
+            explorer_api: Some("https://api.era-gateway-stage.explorer.zksync.dev".into()),
+            explorer_api_key: None,
+            multicall3: MULTICALL3_ADDRESS,
+            ws_rpc: None,
         },
         Ecosystem {
             rpc: "https://rpc.era-gateway-testnet.zksync.dev/".into(),
             bridgehub: Address::from_str("0x0000000000000000000000000000000000010002").unwrap(),
             name: "testnet gateway".into(),
+            explorer_api: Some("https://api.era-gateway-testnet.explorer.zksync.dev".into()),
+            explorer_api_key: None,
+            multicall3: MULTICALL3_ADDRESS,
+            ws_rpc: None,
         },
         Ecosystem {
             rpc: "https://rpc.era-gateway-mainnet.zksync.dev/".into(),
             bridgehub: Address::from_str("0x0000000000000000000000000000000000010002").unwrap(),
             name: "mainnet gateway".into(),
+            explorer_api: Some("https://api.era-gateway-mainnet.explorer.zksync.dev".into()),
+            explorer_api_key: None,
+            multicall3: MULTICALL3_ADDRESS,
+            ws_rpc: None,
         },
         Ecosystem {
             rpc: "https://ethereum-sepolia-rpc.publicnode.com".into(),
             bridgehub: Address::from_str("0x236D1c3Ff32Bd0Ca26b72Af287E895627c0478cE").unwrap(),
             name: "stage".into(),
+            explorer_api: Some("https://api-sepolia.etherscan.io".into()),
+            explorer_api_key: explorer_api_key.clone(),
+            multicall3: MULTICALL3_ADDRESS,
+            ws_rpc: Some("wss://ethereum-sepolia-rpc.publicnode.com".into()),
         },
         Ecosystem {
             rpc: "https://ethereum-sepolia-rpc.publicnode.com".into(),
             bridgehub: Address::from_str("0x35A54c8C757806eB6820629bc82d90E056394C92").unwrap(),
             name: "testnet".into(),
+            explorer_api: Some("https://api-sepolia.etherscan.io".into()),
+            explorer_api_key: explorer_api_key.clone(),
+            multicall3: MULTICALL3_ADDRESS,
+            ws_rpc: Some("wss://ethereum-sepolia-rpc.publicnode.com".into()),
         },
         Ecosystem {
             rpc: "https://ethereum.publicnode.com".into(),
             bridgehub: Address::from_str("0x303a465B659cBB0ab36eE643eA362c509EEb5213").unwrap(),
             name: "mainnet".into(),
+            explorer_api: Some("https://api.etherscan.io".into()),
+            explorer_api_key: explorer_api_key.clone(),
+            multicall3: MULTICALL3_ADDRESS,
+            ws_rpc: Some("wss://ethereum.publicnode.com".into()),
         },
     ];
 
+    if cfg.subscribe {
+        return subscribe_chains(&cfg, &explorer, &configs, &chain_mapping).await;
+    }
+
+    if let Some(interval) = cfg.watch {
+        let baseline = load_snapshot(&cfg.out);
+        return watch_loop(
+            &cfg,
+            &explorer,
+            &configs,
+            &chain_mapping,
+            interval,
+            baseline,
+        )
+        .await;
+    }
+
+    let out = run_once(&explorer, &configs, &chain_mapping, cfg.trusted_block_hash).await?;
+
+    if let Some(diff_path) = &cfg.diff {
+        let previous: Output = serde_json::from_str(
+            &fs::read_to_string(diff_path)
+                .with_context(|| format!("reading {}", diff_path.display()))?,
+        )
+        .with_context(|| format!("parsing {}", diff_path.display()))?;
+        let changelog = diff::diff(&previous, &out);
+        if changelog.is_empty() {
+            println!("No changes since {}", diff_path.display());
+        } else {
+            println!("{}", serde_json::to_string_pretty(&changelog)?);
+        }
+    }
+
+    if let Some(parent) = cfg.out.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    fs::write(&cfg.out, serde_json::to_vec_pretty(&out)?)
+        .with_context(|| format!("writing {}", cfg.out.display()))?;
+
+    println!("Wrote {}", cfg.out.display());
+    Ok(())
+}
+
+/// Fetches the current Bridgehub state across every configured ecosystem
+/// and returns it as a single sorted snapshot.
+async fn run_once(
+    explorer: &ExplorerClient,
+    configs: &[Ecosystem],
+    chain_mapping: &HashMap<String, String>,
+    trusted_block_hash: Option<B256>,
+) -> Result<Output> {
     let mut all_items = HashMap::new();
 
-    for cfg in configs {
-        println!("Processing {}", cfg.name);
+    for ecosystem in configs {
+        println!("Processing {}", ecosystem.name);
         let chain_items =
-            fetch_bridgehub_chains(&cfg.rpc, &cfg.name, cfg.bridgehub, &chain_mapping).await?;
+            fetch_bridgehub_chains(ecosystem, explorer, chain_mapping, trusted_block_hash).await?;
         all_items.extend(chain_items);
     }
 
     let mut sorted: Vec<_> = all_items.into_iter().collect();
     sorted.sort_by(|(a, _), (b, _)| a.cmp(b));
-    let sorted = sorted
-        .into_iter()
-        .collect::<std::collections::BTreeMap<_, _>>();
+    let sorted = sorted.into_iter().collect::<BTreeMap<_, _>>();
 
-    let out = Output {
+    Ok(Output {
         source: "bridgehub".to_string(),
         fetched_at: Utc::now(),
         items: sorted,
-    };
+    })
+}
 
-    if let Some(parent) = cfg.out.parent() {
+/// Best-effort load of a previously written `Output` snapshot; `None` if
+/// the file doesn't exist yet or isn't a valid snapshot (e.g. `out` is
+/// already an append-only changelog from a prior `--watch` run).
+fn load_snapshot(path: &PathBuf) -> Option<Output> {
+    let data = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+/// Re-fetches every `interval_secs` and appends a [`diff::ChangeLog`] line
+/// to `cfg.out` each time something changed, so the file becomes an
+/// append-only history of Bridgehub mutations instead of a single
+/// snapshot. `baseline` seeds the comparison; if `None` (first ever run),
+/// the first fetch establishes the baseline without emitting a changelog.
+async fn watch_loop(
+    cfg: &Config,
+    explorer: &ExplorerClient,
+    configs: &[Ecosystem],
+    chain_mapping: &HashMap<String, String>,
+    interval_secs: u64,
+    mut baseline: Option<Output>,
+) -> Result<()> {
+    loop {
+        let current = run_once(explorer, configs, chain_mapping, cfg.trusted_block_hash).await?;
+
+        match &baseline {
+            Some(previous) => {
+                let changelog = diff::diff(previous, &current);
+                if !changelog.is_empty() {
+                    println!(
+                        "[{}] {} added, {} removed, {} changed",
+                        changelog.fetched_at,
+                        changelog.added.len(),
+                        changelog.removed.len(),
+                        changelog.changed.len()
+                    );
+                    append_changelog(&cfg.out, &changelog)?;
+                }
+            }
+            None => println!(
+                "[{}] established baseline ({} items)",
+                current.fetched_at,
+                current.items.len()
+            ),
+        }
+
+        baseline = Some(current);
+        tokio::time::sleep(Duration::from_secs(interval_secs)).await;
+    }
+}
+
+fn append_changelog(path: &PathBuf, changelog: &diff::ChangeLog) -> Result<()> {
+    if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).ok();
     }
-    fs::write(&cfg.out, serde_json::to_vec_pretty(&out)?)
-        .with_context(|| format!("writing {}", cfg.out.display()))?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("opening {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(changelog)?)?;
+    Ok(())
+}
 
-    println!("Wrote {}", cfg.out.display());
+/// Runs one `subscribe_ecosystem` task per configured ecosystem that has a
+/// `ws_rpc`, each upserting newly-registered chains into a shared `cfg.out`
+/// snapshot as they arrive instead of requiring a full re-scan. Ecosystems
+/// without a `ws_rpc` are skipped with a log line.
+async fn subscribe_chains(
+    cfg: &Config,
+    explorer: &ExplorerClient,
+    configs: &[Ecosystem],
+    chain_mapping: &HashMap<String, String>,
+) -> Result<()> {
+    let out = Arc::new(Mutex::new(load_snapshot(&cfg.out).unwrap_or_else(|| {
+        Output {
+            source: "bridgehub".to_string(),
+            fetched_at: Utc::now(),
+            items: BTreeMap::new(),
+        }
+    })));
+    let explorer = Arc::new(explorer.clone());
+    let start_block = cfg.start_block.unwrap_or(0);
+
+    let mut tasks = FuturesUnordered::new();
+    for ecosystem in configs {
+        let Some(ws_rpc) = ecosystem.ws_rpc.clone() else {
+            println!(
+                "[{}] no ws_rpc configured, skipping subscription",
+                ecosystem.name
+            );
+            continue;
+        };
+        let ecosystem = ecosystem.clone();
+        let explorer = explorer.clone();
+        let chain_mapping = chain_mapping.clone();
+        let out = out.clone();
+        let out_path = cfg.out.clone();
+        tasks.push(tokio::spawn(async move {
+            let name = ecosystem.name.clone();
+            if let Err(e) = subscribe_ecosystem(
+                ecosystem,
+                ws_rpc,
+                start_block,
+                explorer,
+                chain_mapping,
+                out,
+                out_path,
+            )
+            .await
+            {
+                eprintln!("[warn] {name}: subscription ended: {e:#}");
+            }
+        }));
+    }
+
+    if tasks.is_empty() {
+        return Err(anyhow!(
+            "no ecosystem has a ws_rpc configured for --subscribe"
+        ));
+    }
+
+    while tasks.next().await.is_some() {}
+    Ok(())
+}
+
+/// Backfills `NewChain` logs from `start_block` via `eth_getLogs`, then
+/// follows new ones forever via `eth_subscribe("logs", ...)`, upserting
+/// each into `out` and rewriting `out_path` as they arrive.
+async fn subscribe_ecosystem(
+    ecosystem: Ecosystem,
+    ws_rpc: String,
+    start_block: u64,
+    explorer: Arc<ExplorerClient>,
+    chain_mapping: HashMap<String, String>,
+    out: Arc<Mutex<Output>>,
+    out_path: PathBuf,
+) -> Result<()> {
+    let provider = ProviderBuilder::new()
+        .on_ws(WsConnect::new(&ws_rpc))
+        .await
+        .with_context(|| format!("connecting to {ws_rpc}"))?;
+
+    let filter = Filter::new()
+        .address(ecosystem.bridgehub)
+        .event_signature(IBridgehub::NewChain::SIGNATURE_HASH);
+
+    println!(
+        "[{}] backfilling NewChain logs from block {start_block}",
+        ecosystem.name
+    );
+    for log in provider
+        .get_logs(&filter.clone().from_block(start_block))
+        .await?
+    {
+        handle_new_chain_log(
+            &ecosystem,
+            &explorer,
+            &chain_mapping,
+            &provider,
+            &out,
+            &out_path,
+            log,
+        )
+        .await;
+    }
+
+    println!("[{}] subscribed to NewChain logs", ecosystem.name);
+    let subscription = provider.subscribe_logs(&filter).await?;
+    let mut stream = subscription.into_stream();
+    while let Some(log) = stream.next().await {
+        handle_new_chain_log(
+            &ecosystem,
+            &explorer,
+            &chain_mapping,
+            &provider,
+            &out,
+            &out_path,
+            log,
+        )
+        .await;
+    }
     Ok(())
 }
+
+/// Decodes one `NewChain` log, builds its `OutputItem`, and upserts it
+/// into `out`, rewriting `out_path` on success. Decode/verification
+/// failures are logged and otherwise ignored, matching the rest of this
+/// file's best-effort-per-item handling.
+async fn handle_new_chain_log<P: Provider>(
+    ecosystem: &Ecosystem,
+    explorer: &ExplorerClient,
+    chain_mapping: &HashMap<String, String>,
+    provider: &P,
+    out: &Mutex<Output>,
+    out_path: &PathBuf,
+    log: alloy::rpc::types::Log,
+) {
+    let event = match IBridgehub::NewChain::decode_log(&log.inner, true) {
+        Ok(event) => event,
+        Err(e) => {
+            eprintln!("[warn] decoding NewChain log failed: {e:#}");
+            return;
+        }
+    };
+    let trusted = match TrustedBlock::resolve(provider, None).await {
+        Ok(trusted) => trusted,
+        Err(e) => {
+            eprintln!("[warn] resolving trusted block failed: {e:#}");
+            return;
+        }
+    };
+
+    let chain_id = event.chainId.to_string();
+    let chain_name = chain_mapping
+        .get(&chain_id)
+        .cloned()
+        .unwrap_or_else(|| format!("chain_{chain_id}"));
+    let key = format!(
+        "{} DiamondProxy - {} {}",
+        ecosystem.name, chain_id, chain_name
+    );
+    let item = to_output_item(
+        provider,
+        explorer,
+        ecosystem,
+        event.zkChain,
+        &trusted,
+        format!("Diamond Proxy for {chain_id}"),
+    )
+    .await;
+
+    let mut guard = out.lock().await;
+    guard.items.insert(key.clone(), item);
+    guard.fetched_at = Utc::now();
+    match serde_json::to_vec_pretty(&*guard) {
+        Ok(data) => {
+            if let Err(e) = fs::write(out_path, data) {
+                eprintln!("[warn] writing {}: {e:#}", out_path.display());
+            } else {
+                println!("[{}] upserted {key}", guard.fetched_at);
+            }
+        }
+        Err(e) => eprintln!("[warn] serializing {}: {e:#}", out_path.display()),
+    }
+}
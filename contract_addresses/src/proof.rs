@@ -0,0 +1,121 @@
+use alloy::eips::BlockNumberOrTag;
+use alloy::primitives::{keccak256, Address, B256, U256};
+use alloy::providers::Provider;
+use alloy_rlp::RlpEncodable;
+use alloy_trie::{proof::verify_proof, Nibbles};
+use anyhow::{Context, Result};
+
+/// The block we're pinning this run's reads to: its `stateRoot` is what
+/// every account/storage proof gets checked against. Resolving the pin
+/// itself still trusts the RPC -- a full light client would instead check
+/// it against a checkpoint or consensus source, which is out of scope
+/// here.
+#[derive(Debug, Clone, Copy)]
+pub struct TrustedBlock {
+    pub number: u64,
+    pub state_root: B256,
+}
+
+impl TrustedBlock {
+    /// Pins to `trusted_hash` if given, otherwise the chain's latest block.
+    pub async fn resolve<P: Provider>(provider: &P, trusted_hash: Option<B256>) -> Result<Self> {
+        let block = match trusted_hash {
+            Some(hash) => provider
+                .get_block_by_hash(hash)
+                .await?
+                .with_context(|| format!("trusted block {hash} not found"))?,
+            None => provider
+                .get_block_by_number(BlockNumberOrTag::Latest)
+                .await?
+                .context("fetching latest block")?,
+        };
+        Ok(Self {
+            number: block.header.number,
+            state_root: block.header.state_root,
+        })
+    }
+}
+
+/// RLP shape of an account leaf in the state trie: `[nonce, balance,
+/// storageRoot, codeHash]`.
+#[derive(RlpEncodable)]
+struct TrieAccount {
+    nonce: u64,
+    balance: U256,
+    storage_root: B256,
+    code_hash: B256,
+}
+
+/// Fetches `eth_getProof` for `address` (plus any `storage_keys`) at
+/// `trusted.number` and verifies the account proof, and each storage
+/// proof, against `trusted.state_root` by walking the Merkle-Patricia
+/// trie: keccak the address/slot to get the path, and confirm the
+/// terminal trie node encodes the claimed value.
+///
+/// `storage_keys` will usually be empty here -- the values we publish
+/// (`admin`, `sharedBridge`, mapping/array reads like `getZKChain`) live at
+/// storage slots this tool doesn't know without the implementation's
+/// storage layout. Proving the account itself is legitimate against the
+/// trusted state root, then trusting `eth_call` pinned to that same
+/// block, is the fallback the verification layer uses for those reads.
+/// Never errors the whole run -- a bad or lying RPC just loses its
+/// `account_exists` flag.
+pub async fn verify_account<P: Provider>(
+    provider: &P,
+    address: Address,
+    storage_keys: &[B256],
+    trusted: &TrustedBlock,
+) -> bool {
+    match try_verify_account(provider, address, storage_keys, trusted).await {
+        Ok(ok) => ok,
+        Err(e) => {
+            eprintln!("[warn] proof verification for {address} failed: {e:#}");
+            false
+        }
+    }
+}
+
+async fn try_verify_account<P: Provider>(
+    provider: &P,
+    address: Address,
+    storage_keys: &[B256],
+    trusted: &TrustedBlock,
+) -> Result<bool> {
+    let proof = provider
+        .get_proof(address, storage_keys.to_vec())
+        .block_id(trusted.number.into())
+        .await?;
+
+    let account_key = Nibbles::unpack(keccak256(address));
+    let account_rlp = alloy_rlp::encode(&TrieAccount {
+        nonce: proof.nonce,
+        balance: proof.balance,
+        storage_root: proof.storage_hash,
+        code_hash: proof.code_hash,
+    });
+
+    if verify_proof(
+        trusted.state_root,
+        account_key,
+        Some(account_rlp),
+        &proof.account_proof,
+    )
+    .is_err()
+    {
+        return Ok(false);
+    }
+
+    for sp in &proof.storage_proof {
+        let key = Nibbles::unpack(keccak256(sp.key.as_b256()));
+        let expected = if sp.value.is_zero() {
+            None
+        } else {
+            Some(alloy_rlp::encode(&sp.value))
+        };
+        if verify_proof(proof.storage_hash, key, expected, &sp.proof).is_err() {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,308 @@
+//! Configurable JSON extraction rules shared by `airbender_hashes` and
+//! `prover_commits`, so retargeting either scanner at a new commitment
+//! format is a config change instead of a code change.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use std::{fs, path::Path};
+
+/// One way to pull a value out of a parsed JSON document.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Rule {
+    /// Matches any string value whose entire text matches `pattern`,
+    /// wherever it occurs in the tree (e.g. `collect_hashes`'s old
+    /// `^0x[0-9a-fA-F]{64}$`).
+    MatchRegex { pattern: String, label: String },
+    /// Matches any object key named `key`, wherever it occurs in the tree
+    /// (e.g. the old hardcoded `bytecode_hash_hex` / `params_hex` lookup).
+    /// `rename`, if set, replaces `key` in the output path -- e.g. airbender
+    /// matches the `bytecode_hash_hex` key but keeps emitting it under the
+    /// `bytecode` path it used before this rule engine existed.
+    MatchKey {
+        key: String,
+        label: String,
+        #[serde(default)]
+        rename: Option<String>,
+    },
+    /// Selects a single value by JSON-pointer path (RFC 6901), e.g.
+    /// `/commitments/0/hash`.
+    JsonPointer { pointer: String, label: String },
+}
+
+/// A set of rules to run against a document, in order. All matching rules
+/// contribute to the result; rules are not mutually exclusive.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    pub fn load(path: &Path) -> Result<Self> {
+        let data =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&data).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn push_match_regex(&mut self, pattern: String, label: String) {
+        self.rules.push(Rule::MatchRegex { pattern, label });
+    }
+
+    pub fn push_match_key(&mut self, key: String, label: String, rename: Option<String>) {
+        self.rules.push(Rule::MatchKey { key, label, rename });
+    }
+
+    pub fn push_select(&mut self, label: String, pointer: String) {
+        self.rules.push(Rule::JsonPointer { pointer, label });
+    }
+}
+
+/// One value pulled out of a document by a [`Rule`].
+#[derive(Debug, Clone)]
+pub struct ExtractedItem {
+    /// Path (or, for `JsonPointer` rules, the rule's label) locating the
+    /// value within the document, its segments joined with the `separator`
+    /// passed to [`extract`]; used as (part of) the output key.
+    pub path: String,
+    pub value: String,
+    pub label: String,
+}
+
+/// Runs every rule in `rules` against `root` and returns everything that
+/// matched. `path_prefix` is prepended to each `ExtractedItem::path` (the
+/// scanners use it to namespace entries by directory/tag). `separator`
+/// joins path segments below `path_prefix` -- callers pass whatever their
+/// output keys used before this rule engine existed (prover's nested keys
+/// were `.`-joined, airbender's were `/`-joined).
+pub fn extract(
+    path_prefix: &str,
+    root: &Value,
+    rules: &RuleSet,
+    separator: &str,
+) -> Result<Vec<ExtractedItem>> {
+    let mut out = Vec::new();
+    for rule in &rules.rules {
+        match rule {
+            Rule::MatchRegex { pattern, label } => {
+                let re = Regex::new(pattern)
+                    .with_context(|| format!("invalid --match-regex pattern '{pattern}'"))?;
+                collect_by_regex(path_prefix, root, &re, label, separator, &mut out);
+            }
+            Rule::MatchKey { key, label, rename } => {
+                collect_by_key(
+                    path_prefix,
+                    root,
+                    key,
+                    label,
+                    rename.as_deref(),
+                    separator,
+                    &mut out,
+                );
+            }
+            Rule::JsonPointer { pointer, label } => {
+                if let Some(Value::String(s)) = root.pointer(pointer) {
+                    out.push(ExtractedItem {
+                        path: join_path(path_prefix, label, separator),
+                        value: s.clone(),
+                        label: label.clone(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn join_path(prefix: &str, suffix: &str, separator: &str) -> String {
+    if prefix.is_empty() {
+        suffix.to_string()
+    } else {
+        format!("{prefix}{separator}{suffix}")
+    }
+}
+
+fn collect_by_regex(
+    prefix: &str,
+    v: &Value,
+    re: &Regex,
+    label: &str,
+    separator: &str,
+    out: &mut Vec<ExtractedItem>,
+) {
+    match v {
+        Value::String(s) => {
+            if re.is_match(s) {
+                out.push(ExtractedItem {
+                    path: prefix.to_string(),
+                    value: s.clone(),
+                    label: label.to_string(),
+                });
+            }
+        }
+        Value::Array(arr) => {
+            for (i, x) in arr.iter().enumerate() {
+                collect_by_regex(&format!("{prefix}[{i}]"), x, re, label, separator, out);
+            }
+        }
+        Value::Object(map) => {
+            for (k, x) in map {
+                collect_by_regex(
+                    &join_path(prefix, k, separator),
+                    x,
+                    re,
+                    label,
+                    separator,
+                    out,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn collect_by_key(
+    prefix: &str,
+    v: &Value,
+    key: &str,
+    label: &str,
+    rename: Option<&str>,
+    separator: &str,
+    out: &mut Vec<ExtractedItem>,
+) {
+    match v {
+        Value::Object(map) => {
+            for (k, vv) in map {
+                if k == key {
+                    if let Value::String(s) = vv {
+                        out.push(ExtractedItem {
+                            path: join_path(prefix, rename.unwrap_or(k), separator),
+                            value: s.clone(),
+                            label: label.to_string(),
+                        });
+                    }
+                }
+                collect_by_key(
+                    &join_path(prefix, k, separator),
+                    vv,
+                    key,
+                    label,
+                    rename,
+                    separator,
+                    out,
+                );
+            }
+        }
+        Value::Array(arr) => {
+            for (i, x) in arr.iter().enumerate() {
+                collect_by_key(
+                    &format!("{prefix}[{i}]"),
+                    x,
+                    key,
+                    label,
+                    rename,
+                    separator,
+                    out,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paths(items: &[ExtractedItem]) -> Vec<&str> {
+        items.iter().map(|i| i.path.as_str()).collect()
+    }
+
+    /// Matches `collect_hashes`' old behavior: any `0x`-prefixed 64-hex-digit
+    /// string, wherever it occurs, regardless of key name.
+    #[test]
+    fn match_regex_finds_any_matching_string_in_the_tree() {
+        let mut rules = RuleSet::default();
+        rules.push_match_regex(
+            r"^0x[0-9a-fA-F]{64}$".to_string(),
+            "Boojum Hash".to_string(),
+        );
+        let doc = json!({
+            "a": { "b": "0x1111111111111111111111111111111111111111111111111111111111111111" },
+            "c": "not a hash",
+            "d": "0x2222222222222222222222222222222222222222222222222222222222222222",
+        });
+
+        let found = extract("dir", &doc, &rules, ".").unwrap();
+
+        assert_eq!(paths(&found), vec!["dir.a.b", "dir.d"]);
+        assert!(found.iter().all(|i| i.label == "Boojum Hash"));
+    }
+
+    /// Matches `find_string_by_key`'s old output shape: the matched key is
+    /// renamed in the output path, not kept as-is.
+    #[test]
+    fn match_key_with_rename_relabels_the_output_path() {
+        let mut rules = RuleSet::default();
+        rules.push_match_key(
+            "bytecode_hash_hex".to_string(),
+            "Bytecode hash".to_string(),
+            Some("bytecode".to_string()),
+        );
+        let doc = json!({ "bytecode_hash_hex": "0xdeadbeef" });
+
+        let found = extract("v1.0.0/verifier.json", &doc, &rules, "/").unwrap();
+
+        assert_eq!(paths(&found), vec!["v1.0.0/verifier.json/bytecode"]);
+        assert_eq!(found[0].value, "0xdeadbeef");
+    }
+
+    /// Unlike `find_string_by_key`, which returned only the first match,
+    /// `collect_by_key` emits every matching key in the tree -- this is a
+    /// deliberate behavior change from the pre-refactor helper, not a bug.
+    #[test]
+    fn match_key_emits_every_match_not_just_the_first() {
+        let mut rules = RuleSet::default();
+        rules.push_match_key("params_hex".to_string(), "Params hash".to_string(), None);
+        let doc = json!({
+            "a": { "params_hex": "0x1111" },
+            "b": { "params_hex": "0x2222" },
+        });
+
+        let found = extract("", &doc, &rules, "/").unwrap();
+
+        assert_eq!(paths(&found), vec!["a/params_hex", "b/params_hex"]);
+    }
+
+    /// `prover_commits` nests its output keys with `.`; `airbender_hashes`
+    /// uses `/`. Both are just the `separator` passed to `extract()`.
+    #[test]
+    fn separator_controls_how_nested_path_segments_join() {
+        let mut rules = RuleSet::default();
+        rules.push_match_regex(r"^0xdead$".to_string(), "Match".to_string());
+        let doc = json!({ "nested": { "key": "0xdead" } });
+
+        let dot = extract("dir", &doc, &rules, ".").unwrap();
+        let slash = extract("dir", &doc, &rules, "/").unwrap();
+
+        assert_eq!(paths(&dot), vec!["dir.nested.key"]);
+        assert_eq!(paths(&slash), vec!["dir/nested/key"]);
+    }
+
+    /// `JsonPointer` rules ignore `separator` for the selected value itself
+    /// (the pointer is absolute), but still use it to join onto `path_prefix`.
+    #[test]
+    fn json_pointer_rule_selects_by_absolute_path() {
+        let mut rules = RuleSet::default();
+        rules.push_select("Boojum Hash".to_string(), "/commitments/0/hash".to_string());
+        let doc = json!({ "commitments": [{ "hash": "0xabc" }] });
+
+        let found = extract("dir", &doc, &rules, "/").unwrap();
+
+        assert_eq!(paths(&found), vec!["dir/Boojum Hash"]);
+        assert_eq!(found[0].value, "0xabc");
+    }
+}
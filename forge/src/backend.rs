@@ -0,0 +1,341 @@
+use crate::cache::EtagCache;
+use crate::ratelimit;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::header::{HeaderValue, ACCEPT, AUTHORIZATION};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// One entry returned by [`ForgeBackend::list_dir`].
+#[derive(Debug, Clone)]
+pub struct DirEntry {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+}
+
+/// Abstracts the handful of read-only git-forge operations the scanner
+/// needs, so the same tag-sweeping logic can run against GitHub or a
+/// self-hosted Gitea/Forgejo mirror.
+#[async_trait]
+pub trait ForgeBackend: Send + Sync {
+    /// Lists the immediate children of `path` at `git_ref`. `None` if the
+    /// path doesn't exist at that ref.
+    async fn list_dir(&self, path: &str, git_ref: &str) -> Result<Option<Vec<DirEntry>>>;
+
+    /// Lists all tag names (unfiltered; callers apply their own prefix).
+    async fn list_tags(&self) -> Result<Vec<String>>;
+
+    /// Fetches the raw contents of `path` at `git_ref`. `None` if missing.
+    async fn fetch_raw(&self, path: &str, git_ref: &str) -> Result<Option<String>>;
+
+    /// A human-browsable URL for `path` at `git_ref`, used in `OutputItem::url`.
+    fn browse_url(&self, path: &str, git_ref: &str) -> String;
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ContentKind {
+    File,
+    Dir,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentItem {
+    name: String,
+    path: String,
+    #[serde(rename = "type")]
+    kind: ContentKind,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagItem {
+    name: String,
+}
+
+fn auth_header(token: &Option<String>) -> Option<HeaderValue> {
+    token
+        .as_ref()
+        .filter(|t| !t.is_empty())
+        .and_then(|t| HeaderValue::from_str(&format!("Bearer {t}")).ok())
+}
+
+/// Talks to `api.github.com` / `raw.githubusercontent.com`.
+pub struct GithubBackend {
+    owner: String,
+    repo: String,
+    token: Option<String>,
+    client: reqwest::Client,
+    cache: Arc<Mutex<EtagCache>>,
+}
+
+impl GithubBackend {
+    pub fn new(
+        owner: String,
+        repo: String,
+        token: Option<String>,
+        client: reqwest::Client,
+        cache: Arc<Mutex<EtagCache>>,
+    ) -> Self {
+        Self {
+            owner,
+            repo,
+            token,
+            client,
+            cache,
+        }
+    }
+}
+
+#[async_trait]
+impl ForgeBackend for GithubBackend {
+    async fn list_dir(&self, path: &str, git_ref: &str) -> Result<Option<Vec<DirEntry>>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/contents/{}?ref={}",
+            self.owner, self.repo, path, git_ref
+        );
+        let token = self.token.clone();
+        let body = EtagCache::get(&self.cache, &self.client, &url, |mut req| {
+            req = req.header(ACCEPT, "application/vnd.github+json");
+            if let Some(h) = auth_header(&token) {
+                req = req.header(AUTHORIZATION, h);
+            }
+            req
+        })
+        .await?;
+        let Some(body) = body else {
+            return Ok(None);
+        };
+        let items: Vec<ContentItem> = serde_json::from_str(&body)?;
+        Ok(Some(
+            items
+                .into_iter()
+                .map(|i| DirEntry {
+                    name: i.name,
+                    path: i.path,
+                    is_dir: matches!(i.kind, ContentKind::Dir),
+                })
+                .collect(),
+        ))
+    }
+
+    async fn list_tags(&self) -> Result<Vec<String>> {
+        let mut page = 1usize;
+        let per_page = 100usize;
+        let mut out = Vec::new();
+        loop {
+            let url = format!(
+                "https://api.github.com/repos/{}/{}/tags?per_page={}&page={}",
+                self.owner, self.repo, per_page, page
+            );
+            let token = self.token.clone();
+            let client = &self.client;
+            let resp = ratelimit::send_with_retry(|| {
+                let mut req = client
+                    .get(&url)
+                    .header(ACCEPT, "application/vnd.github+json");
+                if let Some(h) = auth_header(&token) {
+                    req = req.header(AUTHORIZATION, h);
+                }
+                req
+            })
+            .await?
+            .error_for_status()?;
+            let batch: Vec<TagItem> = resp.json().await?;
+            if batch.is_empty() {
+                break;
+            }
+            out.extend(batch.into_iter().map(|t| t.name));
+            page += 1;
+        }
+        Ok(out)
+    }
+
+    async fn fetch_raw(&self, path: &str, git_ref: &str) -> Result<Option<String>> {
+        let url = format!(
+            "https://raw.githubusercontent.com/{}/{}/{}/{}",
+            self.owner, self.repo, git_ref, path
+        );
+        let token = self.token.clone();
+        EtagCache::get(&self.cache, &self.client, &url, |mut req| {
+            if let Some(h) = auth_header(&token) {
+                req = req.header(AUTHORIZATION, h);
+            }
+            req
+        })
+        .await
+    }
+
+    fn browse_url(&self, path: &str, git_ref: &str) -> String {
+        format!(
+            "https://github.com/{}/{}/blob/{}/{}",
+            self.owner, self.repo, git_ref, path
+        )
+    }
+}
+
+/// Talks to a Gitea/Forgejo instance's API (`/api/v1/repos/...`) and its
+/// `/{owner}/{repo}/raw/...` raw-content endpoint.
+pub struct GiteaBackend {
+    endpoint: String, // e.g. https://gitea.example.com
+    owner: String,
+    repo: String,
+    token: Option<String>,
+    client: reqwest::Client,
+    cache: Arc<Mutex<EtagCache>>,
+}
+
+impl GiteaBackend {
+    pub fn new(
+        endpoint: String,
+        owner: String,
+        repo: String,
+        token: Option<String>,
+        client: reqwest::Client,
+        cache: Arc<Mutex<EtagCache>>,
+    ) -> Self {
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            owner,
+            repo,
+            token,
+            client,
+            cache,
+        }
+    }
+
+    fn auth_token_header(&self) -> Option<HeaderValue> {
+        self.token
+            .as_ref()
+            .filter(|t| !t.is_empty())
+            .and_then(|t| HeaderValue::from_str(&format!("token {t}")).ok())
+    }
+}
+
+#[async_trait]
+impl ForgeBackend for GiteaBackend {
+    async fn list_dir(&self, path: &str, git_ref: &str) -> Result<Option<Vec<DirEntry>>> {
+        let url = format!(
+            "{}/api/v1/repos/{}/{}/contents/{}?ref={}",
+            self.endpoint, self.owner, self.repo, path, git_ref
+        );
+        let header = self.auth_token_header();
+        let body = EtagCache::get(&self.cache, &self.client, &url, |mut req| {
+            if let Some(h) = &header {
+                req = req.header(AUTHORIZATION, h.clone());
+            }
+            req
+        })
+        .await?;
+        let Some(body) = body else {
+            return Ok(None);
+        };
+        let items: Vec<ContentItem> = serde_json::from_str(&body)?;
+        Ok(Some(
+            items
+                .into_iter()
+                .map(|i| DirEntry {
+                    name: i.name,
+                    path: i.path,
+                    is_dir: matches!(i.kind, ContentKind::Dir),
+                })
+                .collect(),
+        ))
+    }
+
+    async fn list_tags(&self) -> Result<Vec<String>> {
+        let mut page = 1usize;
+        let limit = 50usize;
+        let mut out = Vec::new();
+        loop {
+            let url = format!(
+                "{}/api/v1/repos/{}/{}/tags?limit={}&page={}",
+                self.endpoint, self.owner, self.repo, limit, page
+            );
+            let header = self.auth_token_header();
+            let client = &self.client;
+            let resp = ratelimit::send_with_retry(|| {
+                let mut req = client.get(&url);
+                if let Some(h) = &header {
+                    req = req.header(AUTHORIZATION, h.clone());
+                }
+                req
+            })
+            .await?
+            .error_for_status()?;
+            let batch: Vec<TagItem> = resp.json().await?;
+            if batch.is_empty() {
+                break;
+            }
+            out.extend(batch.into_iter().map(|t| t.name));
+            page += 1;
+        }
+        Ok(out)
+    }
+
+    async fn fetch_raw(&self, path: &str, git_ref: &str) -> Result<Option<String>> {
+        let url = format!(
+            "{}/{}/{}/raw/{}/{}",
+            self.endpoint, self.owner, self.repo, git_ref, path
+        );
+        let header = self.auth_token_header();
+        EtagCache::get(&self.cache, &self.client, &url, |mut req| {
+            if let Some(h) = &header {
+                req = req.header(AUTHORIZATION, h.clone());
+            }
+            req
+        })
+        .await
+    }
+
+    fn browse_url(&self, path: &str, git_ref: &str) -> String {
+        format!(
+            "{}/{}/{}/src/{}/{}",
+            self.endpoint, self.owner, self.repo, git_ref, path
+        )
+    }
+}
+
+/// Which forge kind to talk to, selected via `--forge`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForgeKind {
+    Github,
+    Gitea,
+}
+
+impl std::str::FromStr for ForgeKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "github" => Ok(ForgeKind::Github),
+            "gitea" => Ok(ForgeKind::Gitea),
+            other => Err(anyhow!("unknown forge '{other}', expected github|gitea")),
+        }
+    }
+}
+
+/// Builds the backend selected by `--forge`/`--endpoint`.
+pub fn build_backend(
+    kind: ForgeKind,
+    endpoint: Option<String>,
+    owner: String,
+    repo: String,
+    token: Option<String>,
+    client: reqwest::Client,
+    cache: Arc<Mutex<EtagCache>>,
+) -> Result<Box<dyn ForgeBackend>> {
+    match kind {
+        ForgeKind::Github => Ok(Box::new(GithubBackend::new(
+            owner, repo, token, client, cache,
+        ))),
+        ForgeKind::Gitea => {
+            let endpoint =
+                endpoint.ok_or_else(|| anyhow!("--endpoint is required for --forge gitea"))?;
+            Ok(Box::new(GiteaBackend::new(
+                endpoint, owner, repo, token, client, cache,
+            )))
+        }
+    }
+}
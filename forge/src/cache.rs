@@ -0,0 +1,110 @@
+use crate::ratelimit;
+use anyhow::{Context, Result};
+use reqwest::header::{HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::Path};
+use tokio::sync::Mutex;
+
+/// One cached response for a single request URL.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CacheEntry {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: String,
+}
+
+/// Sidecar file mapping request URL -> last-seen response, so repeat runs can
+/// issue conditional requests (`If-None-Match`) instead of re-downloading and
+/// re-parsing everything on every invocation.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct EtagCache {
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl EtagCache {
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("writing {}", path.display()))?;
+        Ok(())
+    }
+
+    /// GET `url`, reusing the cached body on a `304 Not Modified` and
+    /// recording the fresh `ETag`/`Last-Modified` on a `200`. Returns `None`
+    /// on a `404` so callers can treat "path doesn't exist at this ref" the
+    /// same way they did before caching existed. Rate limits and transient
+    /// `5xx`s are retried by [`ratelimit::send_with_retry`].
+    ///
+    /// Takes `cache` as a shared `Mutex` rather than `&mut self`: the lock
+    /// is only held for the brief read of the conditional header and the
+    /// write-back of the result, not across the network round trip.
+    /// Holding it for the whole `await` would serialize every fetch through
+    /// this cache, regardless of how much parallelism the caller intends.
+    pub async fn get(
+        cache: &Mutex<EtagCache>,
+        client: &reqwest::Client,
+        url: &str,
+        build: impl Fn(reqwest::RequestBuilder) -> reqwest::RequestBuilder,
+    ) -> Result<Option<String>> {
+        let conditional: Option<(HeaderName, String)> = {
+            let guard = cache.lock().await;
+            guard.entries.get(url).and_then(|entry| {
+                entry
+                    .etag
+                    .clone()
+                    .map(|v| (IF_NONE_MATCH, v))
+                    .or_else(|| entry.last_modified.clone().map(|v| (IF_MODIFIED_SINCE, v)))
+            })
+        };
+
+        let resp = ratelimit::send_with_retry(|| {
+            let mut req = build(client.get(url));
+            if let Some((header, value)) = &conditional {
+                req = req.header(header.clone(), value.clone());
+            }
+            req
+        })
+        .await?;
+        if resp.status().as_u16() == 404 {
+            return Ok(None);
+        }
+        if resp.status().as_u16() == 304 {
+            let guard = cache.lock().await;
+            let body = guard
+                .entries
+                .get(url)
+                .map(|e| e.body.clone())
+                .context("304 Not Modified but no cached body")?;
+            return Ok(Some(body));
+        }
+
+        let resp = resp.error_for_status()?;
+        let etag = resp
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        let last_modified = resp
+            .headers()
+            .get(LAST_MODIFIED)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        let body = resp.text().await?;
+
+        cache.lock().await.entries.insert(
+            url.to_string(),
+            CacheEntry {
+                etag,
+                last_modified,
+                body: body.clone(),
+            },
+        );
+        Ok(Some(body))
+    }
+}
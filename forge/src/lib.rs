@@ -0,0 +1,11 @@
+//! Git-forge client shared by `airbender_hashes` and `prover_commits`:
+//! lists/fetches files from GitHub or a Gitea/Forgejo-compatible forge,
+//! with ETag-conditional caching and rate-limit-aware retries. Previously
+//! pasted into both binaries; pulled out here so a fix to either only has
+//! to be made once.
+
+pub mod backend;
+pub mod cache;
+pub mod ratelimit;
+
+pub use backend::{build_backend, DirEntry, ForgeBackend, ForgeKind, GiteaBackend, GithubBackend};
@@ -0,0 +1,112 @@
+use anyhow::{anyhow, Result};
+use rand::Rng;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const MAX_RETRIES: u32 = 6;
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Sends `req`, retrying on rate limits and transient errors instead of
+/// letting `error_for_status()` abort the whole run.
+///
+/// - `403`/`429` with `X-RateLimit-Remaining: 0` or a `Retry-After` header:
+///   sleep until the reset time (or the given delay) and retry.
+/// - `202 Accepted` (GitHub still computing a response): short fixed delay,
+///   then retry.
+/// - `5xx`: capped exponential backoff with jitter.
+///
+/// Gives up with an error once `MAX_RETRIES` is exhausted.
+pub async fn send_with_retry(build: impl Fn() -> RequestBuilder) -> Result<Response> {
+    let mut attempt = 0u32;
+    loop {
+        let resp = build().send().await?;
+        let status = resp.status();
+
+        if (status.is_success() && status != StatusCode::ACCEPTED)
+            || status == StatusCode::NOT_MODIFIED
+        {
+            return Ok(resp);
+        }
+
+        if status == StatusCode::ACCEPTED {
+            if attempt >= MAX_RETRIES {
+                return Err(anyhow!(
+                    "giving up after {attempt} retries: still 202 Accepted"
+                ));
+            }
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            attempt += 1;
+            continue;
+        }
+
+        if status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS {
+            if let Some(delay) = rate_limit_delay(&resp) {
+                if attempt >= MAX_RETRIES {
+                    return Err(anyhow!(
+                        "giving up after {attempt} retries: rate limited ({status})"
+                    ));
+                }
+                eprintln!("[warn] rate limited ({status}), sleeping {delay:?} before retry");
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+                continue;
+            }
+        }
+
+        if status.is_server_error() {
+            if attempt >= MAX_RETRIES {
+                return Err(anyhow!(
+                    "giving up after {attempt} retries: server error {status}"
+                ));
+            }
+            let delay = backoff_with_jitter(attempt);
+            eprintln!("[warn] {status}, sleeping {delay:?} before retry");
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+            continue;
+        }
+
+        return Ok(resp);
+    }
+}
+
+/// Computes how long to wait before retrying a rate-limited response, from
+/// `Retry-After` or `X-RateLimit-Reset`/`X-RateLimit-Remaining`.
+fn rate_limit_delay(resp: &Response) -> Option<Duration> {
+    if let Some(retry_after) = resp
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+    {
+        return Some(Duration::from_secs(retry_after));
+    }
+
+    let remaining: Option<u64> = resp
+        .headers()
+        .get("x-ratelimit-remaining")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok());
+    if remaining != Some(0) {
+        return None;
+    }
+
+    let reset: u64 = resp
+        .headers()
+        .get("x-ratelimit-reset")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    Some(Duration::from_secs(reset.saturating_sub(now) + 1))
+}
+
+fn backoff_with_jitter(attempt: u32) -> Duration {
+    let capped = BASE_BACKOFF * 2u32.saturating_pow(attempt);
+    let capped = capped.min(MAX_BACKOFF);
+    let jitter_ms = rand::thread_rng().gen_range(0..=capped.as_millis() as u64 / 2);
+    capped + Duration::from_millis(jitter_ms)
+}
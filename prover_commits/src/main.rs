@@ -1,12 +1,13 @@
 use anyhow::{anyhow, Context, Result};
 use chrono::Utc;
+use extract::RuleSet;
 use futures::stream::{FuturesUnordered, StreamExt};
-use once_cell::sync::Lazy;
-use regex::Regex;
-use reqwest::header::{ACCEPT, AUTHORIZATION};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use serde_json::{json, Value};
-use std::{collections::HashMap, env, fs, path::PathBuf};
+use std::{collections::HashMap, env, fs, path::PathBuf, sync::Arc};
+use tokio::sync::Mutex;
+
+use forge::{cache::EtagCache, ForgeBackend, ForgeKind};
 
 // ---- Config ----
 #[derive(Debug, Clone)]
@@ -17,6 +18,12 @@ struct Config {
     branch: String,    // e.g. "main"
     out_path: PathBuf, // e.g. "commitments.json"
     parallel: usize,
+    cache_path: PathBuf,
+    forge: ForgeKind,
+    endpoint: Option<String>, // required for --forge gitea
+    rules_file: Option<PathBuf>,
+    select: Vec<(String, String)>,      // (label, json-pointer)
+    match_regex: Vec<(String, String)>, // (label, pattern)
 }
 
 #[derive(Debug, Serialize)]
@@ -35,27 +42,27 @@ impl Default for Config {
             branch: "main".into(),
             out_path: PathBuf::from("commitments.json"),
             parallel: 16,
+            cache_path: PathBuf::from(".zk-wtf-cache.json"),
+            forge: ForgeKind::Github,
+            endpoint: None,
+            rules_file: None,
+            select: Vec::new(),
+            match_regex: Vec::new(),
         }
     }
 }
 
-// ---- GitHub API types ----
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "lowercase")] // type: "file" | "dir"
-enum GhItemType {
-    File,
-    Dir,
-}
-
-#[derive(Debug, Deserialize)]
-struct GhContentItem {
-    name: String,
-    #[serde(rename = "type")]
-    kind: GhItemType,
+/// Matches the behavior of the original hardcoded `collect_hashes`: any
+/// `0x`-prefixed 64-hex-digit string, wherever it appears in the document.
+fn default_rules() -> RuleSet {
+    let mut rules = RuleSet::default();
+    rules.push_match_regex(
+        r"^0x[0-9a-fA-F]{64}$".to_string(),
+        "Boojum Hash".to_string(),
+    );
+    rules
 }
 
-static HASH_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^0x[0-9a-fA-F]{64}$").unwrap());
-
 #[tokio::main]
 async fn main() -> Result<()> {
     let mut cfg = Config::default();
@@ -76,6 +83,29 @@ async fn main() -> Result<()> {
                     .parse()
                     .context("--parallel must be usize")?
             }
+            "--cache-path" => {
+                cfg.cache_path = PathBuf::from(args.next().context("--cache-path requires value")?)
+            }
+            "--forge" => cfg.forge = args.next().context("--forge requires value")?.parse()?,
+            "--endpoint" => cfg.endpoint = Some(args.next().context("--endpoint requires value")?),
+            "--rules-file" => {
+                cfg.rules_file = Some(PathBuf::from(
+                    args.next().context("--rules-file requires value")?,
+                ))
+            }
+            "--select" => {
+                let spec = args
+                    .next()
+                    .context("--select requires LABEL=POINTER value")?;
+                let (label, pointer) = spec
+                    .split_once('=')
+                    .context("--select value must be LABEL=POINTER")?;
+                cfg.select.push((label.to_string(), pointer.to_string()));
+            }
+            "--match-regex" => {
+                let pattern = args.next().context("--match-regex requires value")?;
+                cfg.match_regex.push(("Match".to_string(), pattern));
+            }
             _ => eprintln!("Unknown arg: {arg}"),
         }
     }
@@ -87,17 +117,47 @@ async fn main() -> Result<()> {
         ))
         .build()?;
 
+    let cache = Arc::new(Mutex::new(EtagCache::load(&cfg.cache_path)));
+    let token = env::var("GITHUB_TOKEN")
+        .ok()
+        .filter(|t| !t.trim().is_empty());
+    let backend: Arc<dyn ForgeBackend> = Arc::from(forge::build_backend(
+        cfg.forge,
+        cfg.endpoint.clone(),
+        cfg.owner.clone(),
+        cfg.repo.clone(),
+        token,
+        client,
+        cache.clone(),
+    )?);
+
+    let rules = if let Some(path) = &cfg.rules_file {
+        RuleSet::load(path)?
+    } else if cfg.select.is_empty() && cfg.match_regex.is_empty() {
+        default_rules()
+    } else {
+        let mut rules = RuleSet::default();
+        for (label, pointer) in &cfg.select {
+            rules.push_select(label.clone(), pointer.clone());
+        }
+        for (label, pattern) in &cfg.match_regex {
+            rules.push_match_regex(pattern.clone(), label.clone());
+        }
+        rules
+    };
+
     // 1) List subdirectories under base_path
-    let subdirs = list_subdirs(&client, &cfg).await?;
+    let subdirs = list_subdirs(&backend, &cfg).await?;
     println!("Found {} subdirectories", subdirs.len());
 
     // 2) For each subdir, fetch commitment.json (if present) and extract hashes
     let mut futs = FuturesUnordered::new();
     for dir in subdirs {
-        let client = client.clone();
+        let backend = backend.clone();
         let cfg = cfg.clone();
+        let rules = rules.clone();
         futs.push(tokio::spawn(async move {
-            let res = fetch_commitment_and_extract(&client, &cfg, &dir).await;
+            let res = fetch_commitment_and_extract(&backend, &cfg, &rules, &dir).await;
             (dir, res)
         }));
     }
@@ -135,32 +195,22 @@ async fn main() -> Result<()> {
         .with_context(|| format!("writing {}", cfg.out_path.display()))?;
 
     println!("Wrote {}", cfg.out_path.display());
+
+    cache.lock().await.save(&cfg.cache_path)?;
+
     Ok(())
 }
 
-async fn list_subdirs(client: &reqwest::Client, cfg: &Config) -> Result<Vec<String>> {
-    // GitHub contents API for the base_path
-    // GET /repos/{owner}/{repo}/contents/{path}?ref={branch}
-    let url = format!(
-        "https://api.github.com/repos/{}/{}/contents/{}?ref={}",
-        cfg.owner, cfg.repo, cfg.base_path, cfg.branch
-    );
-    let mut req = client
-        .get(&url)
-        .header(ACCEPT, "application/vnd.github+json");
-    if let Ok(token) = env::var("GITHUB_TOKEN") {
-        if !token.trim().is_empty() {
-            req = req.header(AUTHORIZATION, format!("Bearer {}", token));
-        }
-    }
-    let resp = req.send().await?.error_for_status()?;
-    let items: Vec<GhContentItem> = resp.json().await?;
-    let mut out = Vec::new();
-    for it in items.into_iter() {
-        if let GhItemType::Dir = it.kind {
-            out.push(it.name);
-        }
-    }
+async fn list_subdirs(backend: &Arc<dyn ForgeBackend>, cfg: &Config) -> Result<Vec<String>> {
+    let items = backend
+        .list_dir(&cfg.base_path, &cfg.branch)
+        .await?
+        .with_context(|| format!("{}: 404 Not Found", cfg.base_path))?;
+    let out: Vec<String> = items
+        .into_iter()
+        .filter(|i| i.is_dir)
+        .map(|i| i.name)
+        .collect();
     if out.is_empty() {
         return Err(anyhow!(
             "No subdirectories under {}/{}",
@@ -172,65 +222,50 @@ async fn list_subdirs(client: &reqwest::Client, cfg: &Config) -> Result<Vec<Stri
 }
 
 async fn fetch_commitment_and_extract(
-    client: &reqwest::Client,
+    backend: &Arc<dyn ForgeBackend>,
     cfg: &Config,
+    rules: &RuleSet,
     dir: &str,
 ) -> Result<HashMap<String, OutputItem>> {
     // Check directory contents for commitment.json to avoid 404s
-    let list_url = format!(
-        "https://api.github.com/repos/{}/{}/contents/{}/{}?ref={}",
-        cfg.owner, cfg.repo, cfg.base_path, dir, cfg.branch
-    );
-    let mut req = client
-        .get(&list_url)
-        .header(ACCEPT, "application/vnd.github+json");
-    if let Ok(token) = env::var("GITHUB_TOKEN") {
-        if !token.trim().is_empty() {
-            req = req.header(AUTHORIZATION, format!("Bearer {}", token));
-        }
-    }
-    let resp = req.send().await?.error_for_status()?;
-    let items: Vec<GhContentItem> = resp.json().await?;
+    let dir_path = format!("{}/{}", cfg.base_path, dir);
+    let items = backend
+        .list_dir(&dir_path, &cfg.branch)
+        .await?
+        .with_context(|| format!("{dir_path}: 404 Not Found"))?;
 
     let has_commitment = items
         .iter()
-        .any(|i| matches!(i.kind, GhItemType::File) && i.name == "commitments.json");
+        .any(|i| !i.is_dir && i.name == "commitments.json");
     if !has_commitment {
         return Ok(Default::default()); // No commitment.json in this dir
     }
 
     // Fetch raw commitment.json directly (faster than content API b64 decoding)
-    let raw_url = format!(
-        "https://raw.githubusercontent.com/{}/{}/{}/{}/{}/commitments.json",
-        cfg.owner, cfg.repo, cfg.branch, cfg.base_path, dir
-    );
-    let user_url = format!(
-        "https://github.com/{}/{}/blob/{}/{}/{}/commitments.json",
-        cfg.owner, cfg.repo, cfg.branch, cfg.base_path, dir
-    );
-    let mut req = client.get(&raw_url);
-    if let Ok(token) = env::var("GITHUB_TOKEN") {
-        if !token.trim().is_empty() {
-            req = req.header(AUTHORIZATION, format!("Bearer {}", token));
-        }
-    }
-    let text = req.send().await?.error_for_status()?.text().await?;
+    let file_path = format!("{}/commitments.json", dir_path);
+    let user_url = backend.browse_url(&file_path, &cfg.branch);
+    let text = backend
+        .fetch_raw(&file_path, &cfg.branch)
+        .await?
+        .with_context(|| format!("{file_path}: 404 Not Found"))?;
 
     let val: Value =
-        serde_json::from_str(&text).with_context(|| format!("{}: invalid JSON", raw_url))?;
+        serde_json::from_str(&text).with_context(|| format!("{}: invalid JSON", file_path))?;
 
-    let mut hashes = HashMap::new();
-    collect_hashes(dir, &val, &mut hashes);
+    let items = extract::extract(dir, &val, rules, ".")?;
 
-    let result = hashes
+    let result = items
         .into_iter()
-        .map(|(k, v)| {
+        .map(|item| {
             (
-                k.clone(),
+                item.path.clone(),
                 OutputItem {
-                    value: v,
+                    value: item.value,
                     url: user_url.clone(),
-                    description: format!("Boojum Hash for {} version {} in {}", k, dir, cfg.repo),
+                    description: format!(
+                        "{} for {} version {} in {}",
+                        item.label, item.path, dir, cfg.repo
+                    ),
                 },
             )
         })
@@ -238,29 +273,3 @@ async fn fetch_commitment_and_extract(
 
     Ok(result)
 }
-
-fn collect_hashes(prefix: &str, v: &Value, out: &mut HashMap<String, String>) {
-    match v {
-        Value::String(s) => {
-            if HASH_RE.is_match(s) {
-                out.insert(prefix.to_string(), s.clone());
-            }
-        }
-        Value::Array(arr) => {
-            for (i, x) in arr.iter().enumerate() {
-                collect_hashes(&format!("{}[{}]", prefix, i), x, out);
-            }
-        }
-        Value::Object(map) => {
-            for (k, x) in map {
-                let new_prefix = if prefix.is_empty() {
-                    k.clone()
-                } else {
-                    format!("{}.{}", prefix, k)
-                };
-                collect_hashes(&new_prefix, x, out);
-            }
-        }
-        _ => {}
-    }
-}